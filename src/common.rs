@@ -0,0 +1,53 @@
+use std::io;
+use std::mem;
+use std::os::unix::io::RawFd;
+use futures::Poll;
+
+/// Abstraction over the async transport a [`Connection`](crate::Connection) is driven over.
+/// Implemented by callers so `quiche` can be fed arbitrary duplex datagram transports (a
+/// connected `UdpSocket`, a test harness that drops/reorders packets, ...).
+pub trait LossyIo {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error>;
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error>;
+
+    /// Reads a raw socket option, as `getsockopt` would (e.g. `SOL_SOCKET`/`SO_RCVBUF`).
+    fn get_socket_option(&self, level: i32, name: i32) -> io::Result<i32>;
+
+    /// Sets a raw socket option, as `setsockopt` would (e.g. to enable GSO or pacing).
+    fn set_socket_option(&mut self, level: i32, name: i32, value: i32) -> io::Result<()>;
+}
+
+pub fn to_io_error(err: quiche::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+/// `getsockopt` on a raw fd, shared by every [`LossyIo`] implementation backed by a real socket.
+pub fn get_sockopt(fd: RawFd, level: i32, name: i32) -> io::Result<i32> {
+    let mut value: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>() as libc::socklen_t;
+
+    let ret = unsafe {
+        libc::getsockopt(fd, level, name, &mut value as *mut _ as *mut libc::c_void, &mut len)
+    };
+
+    if ret == 0 {
+        Ok(value)
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// `setsockopt` on a raw fd, shared by every [`LossyIo`] implementation backed by a real socket.
+pub fn set_sockopt(fd: RawFd, level: i32, name: i32, value: i32) -> io::Result<()> {
+    let len = mem::size_of::<libc::c_int>() as libc::socklen_t;
+
+    let ret = unsafe {
+        libc::setsockopt(fd, level, name, &value as *const _ as *const libc::c_void, len)
+    };
+
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
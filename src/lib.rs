@@ -1,12 +1,18 @@
 mod common;
 
-use std::{ io, mem };
+use std::mem;
+use std::io::{ self, Read, Write };
 use std::time::Instant;
+use std::net::SocketAddr;
 use std::sync::{ Arc, Mutex };
-use std::collections::HashMap;
+use std::collections::{ HashMap, VecDeque };
 use futures::{ try_ready, Future, Stream, Poll, Async };
+use tokio_io::{ AsyncRead, AsyncWrite };
 use tokio_timer::Delay;
 use tokio_sync::{ mpsc, oneshot };
+use tokio_udp::UdpSocket;
+use rand::RngCore;
+use std::os::unix::io::AsRawFd;
 use common::{ LossyIo, to_io_error };
 
 
@@ -14,8 +20,324 @@ pub struct QuicConnector {
     config: Arc<Mutex<quiche::Config>>
 }
 
+impl QuicConnector {
+    pub fn new(config: Arc<Mutex<quiche::Config>>) -> QuicConnector {
+        QuicConnector { config }
+    }
+
+    /// Starts a client handshake over `io`. `session` is an optional ticket returned by a
+    /// prior [`Connection::session`], enabling 1-RTT resumption and, once the server accepts
+    /// it, 0-RTT writes on the returned [`Connecting`] (see [`Connecting::write_early_data`]).
+    pub fn connect<IO: LossyIo>(
+        &self,
+        io: IO,
+        server_name: Option<&str>,
+        session: Option<&[u8]>
+    ) -> io::Result<Connecting<IO>> {
+        let mut scid = vec![0; quiche::MAX_CONN_ID_LEN];
+        rand::thread_rng().fill_bytes(&mut scid);
+
+        let mut connect = {
+            let mut config = self.config.lock().unwrap();
+            quiche::connect(server_name, &scid, &mut config).map_err(to_io_error)?
+        };
+
+        if let Some(session) = session {
+            connect.set_session(session).map_err(to_io_error)?;
+        }
+
+        // Client bidi streams start at 0, uni at 2, both stepping by 4; see
+        // `Connecting::write_early_data` for why these are tracked from here already.
+        let (next_bidi_id, next_uni_id) = if connect.is_server() { (1, 3) } else { (0, 2) };
+
+        let inner = Inner {
+            io,
+            connect,
+            timer: None,
+            send_buf: vec![0; 65535],
+            send_pos: 0,
+            send_end: 0,
+            send_flush: false,
+            recv_buf: vec![0; 65535]
+        };
+
+        Ok(Connecting {
+            inner: MidHandshake::Handshaking(inner),
+            next_bidi_id,
+            next_uni_id,
+            early_streams: HashMap::new(),
+            conn_guard: None
+        })
+    }
+}
+
+/// A QUIC server endpoint: owns a single UDP socket, performs stateless accept for new
+/// connections and demultiplexes inbound datagrams to the right connection by DCID.
+///
+/// Mirrors the role `QuicConnector` plays on the client side, but yields a [`Stream`] of
+/// in-progress handshakes instead of driving a single one.
+pub struct QuicListener {
+    socket: Arc<UdpSocket>,
+    config: Arc<Mutex<quiche::Config>>,
+    conn_map: HashMap<Vec<u8>, mpsc::UnboundedSender<(Vec<u8>, SocketAddr)>>,
+    recv_buf: Vec<u8>,
+    // Scratch space for the handful of Retry/VersionNegotiation bytes sent on the
+    // stateless-retry path below, reused instead of allocating a fresh buffer per Initial.
+    scratch_buf: Vec<u8>,
+    removal_send: mpsc::UnboundedSender<Vec<u8>>,
+    removal_recv: mpsc::UnboundedReceiver<Vec<u8>>
+}
+
+impl QuicListener {
+    pub fn bind(addr: &SocketAddr, config: Arc<Mutex<quiche::Config>>) -> io::Result<QuicListener> {
+        let socket = UdpSocket::bind(addr)?;
+        let (removal_send, removal_recv) = mpsc::unbounded_channel();
+
+        Ok(QuicListener {
+            socket: Arc::new(socket),
+            config,
+            conn_map: HashMap::new(),
+            recv_buf: vec![0; 65535],
+            scratch_buf: vec![0; 65535],
+            removal_send,
+            removal_recv
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+}
+
+impl Stream for QuicListener {
+    type Item = Connecting<MultiplexedIo>;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            // Prune connections that have fully ended (driven to completion or dropped)
+            // since the last poll, so `conn_map` doesn't grow for the life of the process.
+            while let Ok(Async::Ready(Some(scid))) = self.removal_recv.poll() {
+                self.conn_map.remove(&scid);
+            }
+
+            let (n, from) = try_ready!(self.socket.poll_recv_from(&mut self.recv_buf));
+
+            let hdr = match quiche::Header::from_slice(&mut self.recv_buf[..n], quiche::MAX_CONN_ID_LEN) {
+                Ok(hdr) => hdr,
+                Err(_) => continue
+            };
+
+            if let Some(sender) = self.conn_map.get_mut(&hdr.dcid) {
+                let _ = sender.try_send((self.recv_buf[..n].to_vec(), from));
+                continue;
+            }
+
+            if hdr.ty != quiche::Type::Initial {
+                // Datagram for an unknown connection id that isn't an Initial: nothing we
+                // can do with it, drop it on the floor like quiche's own server example.
+                continue;
+            }
+
+            if !quiche::version_is_supported(hdr.version) {
+                let len = quiche::negotiate_version(&hdr.scid, &hdr.dcid, &mut self.scratch_buf)
+                    .map_err(to_io_error)?;
+                // Best-effort like the `conn_map` dispatch above: if the socket can't take it
+                // right now, the client's own PTO will retransmit the Initial rather than us
+                // blocking every other connection attempt on this one send.
+                let _ = self.socket.poll_send_to(&self.scratch_buf[..len], &from);
+                continue;
+            }
+
+            // Stateless retry: an Initial without a token is unverified, so reply with a Retry
+            // packet carrying an address-bound token instead of accepting it outright. Only a
+            // client that actually owns `from` can echo the token back, so a spoofed or
+            // replayed Initial (lost response, slow accept, flood) can't mint a fresh
+            // `conn_map` entry and `quiche::accept`'d connection on every arrival.
+            let token = hdr.token.as_deref().unwrap_or(&[]);
+
+            if token.is_empty() {
+                let mut retry_scid = vec![0; quiche::MAX_CONN_ID_LEN];
+                rand::thread_rng().fill_bytes(&mut retry_scid);
+
+                let token = mint_token(&hdr, &from);
+
+                let len = quiche::retry(
+                    &hdr.scid, &hdr.dcid, &retry_scid, &token, hdr.version, &mut self.scratch_buf
+                ).map_err(to_io_error)?;
+
+                let _ = self.socket.poll_send_to(&self.scratch_buf[..len], &from);
+                continue;
+            }
+
+            let odcid = match validate_token(&from, token) {
+                Some(odcid) => odcid,
+                // Token doesn't match this source address: forged or stale, drop it rather
+                // than risk it being a spoofed address we'd be amplifying traffic towards.
+                None => continue
+            };
+
+            // Reuse the connection id the Retry handed out (the client's `dcid` now) instead of
+            // minting another, so the client's retransmits of this Initial are routed through
+            // `conn_map` above rather than accepted again as new connections.
+            if hdr.dcid.len() != quiche::MAX_CONN_ID_LEN {
+                continue;
+            }
+
+            let scid = hdr.dcid.to_vec();
+
+            let connect = {
+                let mut config = self.config.lock().unwrap();
+                quiche::accept(&scid, Some(&odcid), &mut config).map_err(to_io_error)?
+            };
+
+            let (dgram_send, dgram_recv) = mpsc::unbounded_channel();
+            self.conn_map.insert(scid.clone(), dgram_send);
+
+            let io = MultiplexedIo {
+                socket: Arc::clone(&self.socket),
+                peer: from,
+                inbound: dgram_recv,
+                read_buf: self.recv_buf[..n].to_vec(),
+                read_pos: 0
+            };
+
+            let inner = Inner {
+                io,
+                connect,
+                timer: None,
+                send_buf: vec![0; 65535],
+                send_pos: 0,
+                send_end: 0,
+                send_flush: false,
+                recv_buf: vec![0; 65535]
+            };
+
+            let conn_guard = ConnGuard { scid: scid.clone(), removal: self.removal_send.clone() };
+
+            return Ok(Async::Ready(Some(Connecting {
+                inner: MidHandshake::Handshaking(inner),
+                // Server bidi streams start at 1, uni at 3, both stepping by 4.
+                next_bidi_id: 1,
+                next_uni_id: 3,
+                early_streams: HashMap::new(),
+                conn_guard: Some(conn_guard)
+            })));
+        }
+    }
+}
+
+/// Builds a retry token binding `hdr`'s original `dcid` to the client's source address, so a
+/// later Initial that echoes it back both proves address ownership and lets `quiche::accept`
+/// recover the `dcid` the very first Initial used.
+///
+/// This only binds the token to the source address, the same tradeoff quiche's own retry
+/// example makes — it is not HMAC-signed or otherwise authenticated, so anyone who knows an
+/// address can build a token that [`validate_token`] accepts for it. Swap in a signed/sealed
+/// token if that threat model matters.
+fn mint_token(hdr: &quiche::Header, from: &SocketAddr) -> Vec<u8> {
+    let mut token = Vec::new();
+
+    token.extend_from_slice(b"quiche-tokio-retry");
+    token.extend_from_slice(&addr_bytes(from));
+    token.extend_from_slice(&hdr.dcid);
+
+    token
+}
+
+/// Validates a token minted by [`mint_token`] against the address it was handed out to,
+/// returning the original `dcid` it embeds on success.
+fn validate_token(from: &SocketAddr, token: &[u8]) -> Option<Vec<u8>> {
+    const PREFIX: &[u8] = b"quiche-tokio-retry";
+
+    let token = token.strip_prefix(PREFIX)?;
+    let addr = addr_bytes(from);
+
+    if token.len() < addr.len() || token[..addr.len()] != addr[..] {
+        return None;
+    }
+
+    Some(token[addr.len()..].to_vec())
+}
+
+fn addr_bytes(addr: &SocketAddr) -> Vec<u8> {
+    match addr.ip() {
+        std::net::IpAddr::V4(v4) => v4.octets().to_vec(),
+        std::net::IpAddr::V6(v6) => v6.octets().to_vec()
+    }
+}
+
+/// Held by a server-side [`Driver`] purely for its `Drop` side effect: tells the owning
+/// [`QuicListener`] to prune `conn_map` once the connection this guard belongs to ends,
+/// however that happens (graceful close, handshake failure, or simply being dropped).
+struct ConnGuard {
+    scid: Vec<u8>,
+    removal: mpsc::UnboundedSender<Vec<u8>>
+}
+
+impl Drop for ConnGuard {
+    fn drop(&mut self) {
+        let _ = self.removal.try_send(self.scid.clone());
+    }
+}
+
+/// The server-side [`LossyIo`] implementation: many connections share one physical
+/// [`UdpSocket`], so each connection only sees the datagrams the [`QuicListener`] routed to it.
+pub struct MultiplexedIo {
+    socket: Arc<UdpSocket>,
+    peer: SocketAddr,
+    inbound: mpsc::UnboundedReceiver<(Vec<u8>, SocketAddr)>,
+    read_buf: Vec<u8>,
+    read_pos: usize
+}
+
+impl LossyIo for MultiplexedIo {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error> {
+        loop {
+            if self.read_pos < self.read_buf.len() {
+                let n = buf.len().min(self.read_buf.len() - self.read_pos);
+                buf[..n].copy_from_slice(&self.read_buf[self.read_pos..self.read_pos + n]);
+                self.read_pos += n;
+
+                return Ok(Async::Ready(n));
+            }
+
+            match self.inbound.poll() {
+                Ok(Async::Ready(Some((datagram, from)))) => {
+                    self.peer = from;
+                    self.read_buf = datagram;
+                    self.read_pos = 0;
+                },
+                Ok(Async::Ready(None)) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(_) => return Err(io::ErrorKind::Other.into())
+            }
+        }
+    }
+
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error> {
+        self.socket.poll_send_to(buf, &self.peer)
+    }
+
+    // `socket` is the one `UdpSocket` the owning `QuicListener` accepted every connection on,
+    // not a handle private to this connection, so these operate on socket state shared by
+    // every other live connection on the server: a caller reading or setting e.g.
+    // `SO_RCVBUF`/pacing here sees or changes it for all of them, not just its own stream.
+    fn get_socket_option(&self, level: i32, name: i32) -> io::Result<i32> {
+        common::get_sockopt(self.socket.as_raw_fd(), level, name)
+    }
+
+    fn set_socket_option(&mut self, level: i32, name: i32, value: i32) -> io::Result<()> {
+        common::set_sockopt(self.socket.as_raw_fd(), level, name, value)
+    }
+}
+
 pub struct Connecting<IO> {
-    inner: MidHandshake<IO>
+    inner: MidHandshake<IO>,
+    next_bidi_id: u64,
+    next_uni_id: u64,
+    early_streams: HashMap<u64, (mpsc::Sender<Message>, mpsc::Receiver<Message>)>,
+    conn_guard: Option<ConnGuard>
 }
 
 enum MidHandshake<IO> {
@@ -23,16 +345,144 @@ enum MidHandshake<IO> {
     End
 }
 
+impl<IO: LossyIo> Connecting<IO> {
+    /// Opens a bidirectional or unidirectional stream and writes 0-RTT application data to
+    /// it before the handshake has completed.
+    ///
+    /// Only valid once the server has accepted early data (`quiche`'s `is_in_early_data`);
+    /// otherwise the data would be silently discarded by the peer, so this returns an error.
+    ///
+    /// The allocated id is drawn from the same counters [`Connection::open_bi`]/`open_uni`
+    /// use once the [`Driver`] takes over, and the returned [`QuicStream`] is wired into the
+    /// `Driver`'s `stream_map` from the moment it starts running, so later reads/writes on it
+    /// (after this handshake resolves) work exactly like any other stream.
+    pub fn write_early_data(&mut self, bidi: bool, buf: &[u8], fin: bool) -> io::Result<QuicStream> {
+        match &mut self.inner {
+            MidHandshake::Handshaking(inner) if inner.connect.is_in_early_data() => {
+                let stream_id = if bidi {
+                    let id = self.next_bidi_id;
+                    self.next_bidi_id += 4;
+                    id
+                } else {
+                    let id = self.next_uni_id;
+                    self.next_uni_id += 4;
+                    id
+                };
+
+                inner.connect.stream_send(stream_id, buf, fin).map_err(to_io_error)?;
+
+                let (net_pair, stream) = stream_channels(stream_id);
+                self.early_streams.insert(stream_id, net_pair);
+
+                Ok(stream)
+            },
+            _ => Err(io::Error::new(io::ErrorKind::Other, "not in early data"))
+        }
+    }
+}
+
 pub struct Connection {
     anchor: Arc<Anchor>,
     trace_id: String,
     alpn: Vec<u8>,
-    is_resumed: bool
+    is_resumed: bool,
+    session: Arc<Mutex<Option<Vec<u8>>>>,
+    control_send: mpsc::UnboundedSender<Control>,
+    datagram_recv: mpsc::UnboundedReceiver<Vec<u8>>,
+    stats: Arc<Mutex<quiche::Stats>>
+}
+
+impl Connection {
+    /// Snapshots the connection's path/transport statistics (RTT, congestion window,
+    /// bytes/packets sent & received, lost packets). Sampled by the `Driver` on every poll,
+    /// so this can be called at any time while the connection is running.
+    pub fn stats(&self) -> quiche::Stats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Returns the negotiated session ticket, if any, so it can be persisted and later
+    /// handed to [`QuicConnector::connect`] to resume this connection with 0-RTT.
+    ///
+    /// The ticket is typically delivered by the peer as a post-handshake message, not during
+    /// the handshake itself, so (like [`Connection::stats`]) this is sampled by the `Driver`
+    /// on every poll rather than captured once when the handshake completes.
+    pub fn session(&self) -> Option<Vec<u8>> {
+        self.session.lock().unwrap().clone()
+    }
+
+    pub fn is_resumed(&self) -> bool {
+        self.is_resumed
+    }
+
+    /// Opens a new outgoing bidirectional stream.
+    pub fn open_bi(&self) -> OpenStream {
+        self.open_stream(true)
+    }
+
+    /// Opens a new outgoing unidirectional stream.
+    pub fn open_uni(&self) -> OpenStream {
+        self.open_stream(false)
+    }
+
+    fn open_stream(&self, bidi: bool) -> OpenStream {
+        let (resp_send, resp_recv) = oneshot::channel();
+        let _ = self.control_send.try_send(Control::OpenStream { bidi, resp: resp_send });
+
+        OpenStream { resp_recv }
+    }
+
+    /// Queues an unreliable DATAGRAM frame for delivery. Requires the connection's
+    /// `quiche::Config` to have been built with `enable_dgram(true, ..)`; the frame is
+    /// dropped by `Driver` if it exceeds the peer's advertised DATAGRAM size limit.
+    pub fn send_datagram(&self, data: &[u8]) -> io::Result<()> {
+        self.control_send.try_send(Control::SendDatagram(data.to_vec()))
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "driver dropped"))
+    }
+
+    /// A `Stream` of unreliable DATAGRAM frames received from the peer.
+    pub fn datagrams(&mut self) -> Datagrams<'_> {
+        Datagrams { rx: &mut self.datagram_recv }
+    }
+}
+
+enum Control {
+    OpenStream { bidi: bool, resp: oneshot::Sender<QuicStream> },
+    SendDatagram(Vec<u8>)
+}
+
+/// Borrowing handle returned by [`Connection::datagrams`].
+pub struct Datagrams<'a> {
+    rx: &'a mut mpsc::UnboundedReceiver<Vec<u8>>
+}
+
+impl<'a> Stream for Datagrams<'a> {
+    type Item = Vec<u8>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.rx.poll().map_err(drop)
+    }
+}
+
+/// Future returned by [`Connection::open_bi`]/[`Connection::open_uni`], resolving once the
+/// [`Driver`] has allocated the stream.
+pub struct OpenStream {
+    resp_recv: oneshot::Receiver<QuicStream>
+}
+
+impl Future for OpenStream {
+    type Item = QuicStream;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        self.resp_recv.poll()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "driver dropped"))
+    }
 }
 
 pub struct Incoming {
     anchor: Arc<Anchor>,
-    rx: mpsc::UnboundedReceiver<QuicStream>
+    rx: mpsc::Receiver<QuicStream>
 }
 
 struct Anchor(Option<oneshot::Sender<()>>);
@@ -45,21 +495,82 @@ impl Drop for Anchor {
     }
 }
 
+/// Bound on the number of buffered [`Message`]s per stream direction. Ties application-level
+/// queue depth to the transport's own flow control instead of letting either side grow
+/// unbounded while the other is slow to drain it.
+const STREAM_BUFFER: usize = 64;
+
+/// Bound on the number of fully-formed streams buffered between the `Driver` and `Incoming`.
+const INCOMING_BUFFER: usize = 16;
+
+/// Creates a bounded channel pair for a stream: the `Driver`-facing half (kept in a
+/// `stream_map` for the pump to drive) and the application-facing [`QuicStream`] handed back
+/// to the caller. Shared by [`Driver::register_stream`] and [`Connecting::write_early_data`],
+/// since the latter has to populate a `stream_map` before any `Driver` exists to do it.
+fn stream_channels(id: u64) -> ((mpsc::Sender<Message>, mpsc::Receiver<Message>), QuicStream) {
+    let (net_tx, app_rx) = mpsc::channel(STREAM_BUFFER);
+    let (app_tx, net_rx) = mpsc::channel(STREAM_BUFFER);
+
+    let stream = QuicStream {
+        id,
+        tx: app_tx,
+        rx: app_rx,
+        read_buf: Vec::new(),
+        read_eof: false,
+        shutdown_end_sent: false
+    };
+
+    ((net_tx, net_rx), stream)
+}
+
 pub struct Driver<IO> {
     inner: Inner<IO>,
     close_recv: oneshot::Receiver<()>,
-    incoming_send: mpsc::UnboundedSender<QuicStream>,
-    stream_map: HashMap<u64, (mpsc::UnboundedSender<Message>, mpsc::UnboundedReceiver<Message>)>
+    control_recv: mpsc::UnboundedReceiver<Control>,
+    incoming_send: mpsc::Sender<QuicStream>,
+    stream_map: HashMap<u64, (mpsc::Sender<Message>, mpsc::Receiver<Message>)>,
+    next_bidi_id: u64,
+    next_uni_id: u64,
+    scratch: Vec<u8>,
+    datagram_send: mpsc::UnboundedSender<Vec<u8>>,
+    datagram_queue: VecDeque<Vec<u8>>,
+    stats: Arc<Mutex<quiche::Stats>>,
+    session: Arc<Mutex<Option<Vec<u8>>>>,
+    conn_guard: Option<ConnGuard>
+}
+
+impl<IO: LossyIo> Driver<IO> {
+    /// Registers a freshly allocated `stream_id` and returns the application-facing
+    /// [`QuicStream`] half, wiring the other half into `stream_map` for the pump to drive.
+    fn register_stream(&mut self, stream_id: u64) -> QuicStream {
+        let (net_pair, stream) = stream_channels(stream_id);
+        self.stream_map.insert(stream_id, net_pair);
+
+        stream
+    }
 }
 
 pub struct QuicStream {
     id: u64,
-    tx: mpsc::UnboundedSender<Message>,
-    rx: mpsc::UnboundedReceiver<Message>
+    tx: mpsc::Sender<Message>,
+    rx: mpsc::Receiver<Message>,
+    read_buf: Vec<u8>,
+    read_eof: bool,
+    // Set once `Message::End` has actually been queued, so a `shutdown` that's interrupted by
+    // backpressure doesn't resend it (and re-truncate the stream) on the next poll.
+    shutdown_end_sent: bool
 }
 
 impl Drop for QuicStream {
     fn drop(&mut self) {
+        // Best-effort, same as `Close` below: if `shutdown` never got far enough to queue
+        // `End` (e.g. cancelled while parked on backpressure), a bare `Close` would abandon
+        // the peer's stream without a FIN. Drop can't park, so this is fire-and-forget rather
+        // than the poll_ready-gated send `shutdown` uses.
+        if !self.shutdown_end_sent {
+            let _ = self.tx.try_send(Message::End(Vec::new()));
+        }
+
         let _ = self.tx.try_send(Message::Close);
     }
 }
@@ -70,6 +581,90 @@ enum Message {
     Close
 }
 
+impl Read for QuicStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if !self.read_buf.is_empty() {
+                let n = buf.len().min(self.read_buf.len());
+                buf[..n].copy_from_slice(&self.read_buf[..n]);
+                self.read_buf.drain(..n);
+
+                return Ok(n);
+            }
+
+            if self.read_eof {
+                return Ok(0);
+            }
+
+            match self.rx.poll() {
+                Ok(Async::Ready(Some(Message::Bytes(bytes)))) => self.read_buf = bytes,
+                Ok(Async::Ready(Some(Message::End(bytes)))) => {
+                    self.read_buf = bytes;
+                    self.read_eof = true;
+                },
+                Ok(Async::Ready(Some(Message::Close))) | Ok(Async::Ready(None)) => {
+                    self.read_eof = true;
+
+                    return Ok(0);
+                },
+                Ok(Async::NotReady) => return Err(io::ErrorKind::WouldBlock.into()),
+                Err(_) => return Err(io::Error::new(io::ErrorKind::Other, "stream receiver closed"))
+            }
+        }
+    }
+}
+
+impl Write for QuicStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // `poll_ready` (unlike `try_send`) registers the current task to be woken once the
+        // `Driver` drains the channel, so a `WouldBlock` here doesn't leave the writer parked
+        // forever the way a bare `try_send` check would.
+        match self.tx.poll_ready() {
+            Ok(Async::Ready(())) => match self.tx.try_send(Message::Bytes(buf.to_vec())) {
+                Ok(()) => Ok(buf.len()),
+                Err(_) => Err(io::Error::new(io::ErrorKind::Other, "stream sender closed"))
+            },
+            Ok(Async::NotReady) => Err(io::ErrorKind::WouldBlock.into()),
+            Err(_) => Err(io::Error::new(io::ErrorKind::Other, "stream sender closed"))
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsyncRead for QuicStream {}
+
+impl AsyncWrite for QuicStream {
+    fn shutdown(&mut self) -> Poll<(), io::Error> {
+        // Same `poll_ready`-before-`try_send` backpressure as `Write::write`: under a full
+        // channel this parks the task and retries on the next poll instead of discarding the
+        // close signal and telling the caller shutdown succeeded when the peer never saw a FIN.
+        if !self.shutdown_end_sent {
+            match self.tx.poll_ready() {
+                Ok(Async::Ready(())) => {
+                    let _ = self.tx.try_send(Message::End(Vec::new()));
+                    self.shutdown_end_sent = true;
+                },
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                // Receiver gone: nothing left to shut down.
+                Err(_) => return Ok(Async::Ready(()))
+            }
+        }
+
+        match self.tx.poll_ready() {
+            Ok(Async::Ready(())) => {
+                let _ = self.tx.try_send(Message::Close);
+
+                Ok(Async::Ready(()))
+            },
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(_) => Ok(Async::Ready(()))
+        }
+    }
+}
+
 struct Inner<IO> {
     io: IO,
     connect: Box<quiche::Connection>,
@@ -185,24 +780,41 @@ impl<IO: LossyIo> Future for Connecting<IO> {
             MidHandshake::Handshaking(inner) => {
                 let (anchor, close_recv) = oneshot::channel();
                 let anchor = Arc::new(Anchor(Some(anchor)));
-                let (incoming_send, incoming_recv) = mpsc::unbounded_channel();
+                let (incoming_send, incoming_recv) = mpsc::channel(INCOMING_BUFFER);
+                let (control_send, control_recv) = mpsc::unbounded_channel();
+                let (datagram_send, datagram_recv) = mpsc::unbounded_channel();
+                let stats = Arc::new(Mutex::new(inner.connect.stats()));
+                let session = Arc::new(Mutex::new(inner.connect.session()));
 
                 let connection = Connection {
                     anchor: Arc::clone(&anchor),
                     trace_id: inner.connect.trace_id().to_string(),
                     alpn: inner.connect.application_proto().to_vec(),
-                    is_resumed: inner.connect.is_resumed()
+                    is_resumed: inner.connect.is_resumed(),
+                    session: Arc::clone(&session),
+                    control_send,
+                    datagram_recv,
+                    stats: Arc::clone(&stats)
                 };
 
                 let incoming = Incoming { anchor, rx: incoming_recv };
 
+                // `next_bidi_id`/`next_uni_id` were seeded when this `Connecting` was created
+                // (and possibly advanced since by `write_early_data`), so 0-RTT streams keep
+                // their ids instead of being handed out again here.
                 let driver = Driver {
-                    inner, close_recv, incoming_send,
-                    stream_map: HashMap::new()
+                    inner, close_recv, control_recv, incoming_send,
+                    stream_map: mem::replace(&mut self.early_streams, HashMap::new()),
+                    next_bidi_id: self.next_bidi_id,
+                    next_uni_id: self.next_uni_id,
+                    scratch: vec![0; 65535],
+                    datagram_send,
+                    datagram_queue: VecDeque::new(),
+                    stats,
+                    session,
+                    conn_guard: self.conn_guard.take()
                 };
 
-                // TODO
-
                 Ok(Async::Ready((driver, connection, incoming)))
             },
             MidHandshake::End => panic!()
@@ -218,13 +830,137 @@ impl<IO: LossyIo> Future for Driver<IO> {
         loop {
             self.inner.poll_complete().map_err(drop)?;
 
-            for stream_id in self.inner.connect.readable() {
-                if !self.stream_map.get(&stream_id).is_some() {
-                    // TODO
+            *self.stats.lock().unwrap() = self.inner.connect.stats();
+            *self.session.lock().unwrap() = self.inner.connect.session();
+
+            while let Ok(Async::Ready(Some(ctrl))) = self.control_recv.poll() {
+                match ctrl {
+                    Control::OpenStream { bidi, resp } => {
+                        let stream_id = if bidi {
+                            let id = self.next_bidi_id;
+                            self.next_bidi_id += 4;
+                            id
+                        } else {
+                            let id = self.next_uni_id;
+                            self.next_uni_id += 4;
+                            id
+                        };
+
+                        let stream = self.register_stream(stream_id);
+                        let _ = resp.send(stream);
+                    },
+                    Control::SendDatagram(data) => self.datagram_queue.push_back(data)
+                }
+            }
+
+            loop {
+                match self.inner.connect.dgram_recv(&mut self.scratch) {
+                    Ok(n) => {
+                        let _ = self.datagram_send.try_send(self.scratch[..n].to_vec());
+                    },
+                    Err(quiche::Error::Done) => break,
+                    Err(_) => break
+                }
+            }
+
+            while let Some(data) = self.datagram_queue.front() {
+                let writable = self.inner.connect.dgram_max_writable_len().unwrap_or(0);
+
+                if data.len() > writable {
+                    // Oversized for the peer's advertised DATAGRAM limit: nothing to do but
+                    // drop it, same as quiche's own dgram example.
+                    self.datagram_queue.pop_front();
+                    continue;
+                }
+
+                match self.inner.connect.dgram_send(data) {
+                    Ok(()) => { self.datagram_queue.pop_front(); },
+                    Err(quiche::Error::Done) => break,
+                    Err(_) => { self.datagram_queue.pop_front(); }
+                }
+            }
+
+            let readable: Vec<u64> = self.inner.connect.readable().collect();
+
+            for stream_id in readable {
+                if self.stream_map.get(&stream_id).is_none() {
+                    if self.incoming_send.poll_ready().map(|p| p.is_not_ready()).unwrap_or(true) {
+                        // No room in the Incoming queue yet; leave the stream unread until
+                        // the application drains it, instead of buffering unboundedly here.
+                        continue;
+                    }
+
+                    let stream = self.register_stream(stream_id);
+                    let _ = self.incoming_send.try_send(stream);
+                }
+
+                loop {
+                    // Only pull bytes off the wire for this stream while its bounded channel
+                    // has room; a full channel means the application reader is slow, so park
+                    // it here and let transport-level flow control apply backpressure.
+                    let ready = match self.stream_map.get_mut(&stream_id) {
+                        Some((tx, _)) => tx.poll_ready().map(|p| p.is_ready()).unwrap_or(false),
+                        None => false
+                    };
+
+                    if !ready {
+                        break;
+                    }
+
+                    match self.inner.connect.stream_recv(stream_id, &mut self.scratch) {
+                        Ok((n, fin)) => {
+                            if let Some((tx, _)) = self.stream_map.get_mut(&stream_id) {
+                                let bytes = self.scratch[..n].to_vec();
+                                let message = if fin { Message::End(bytes) } else { Message::Bytes(bytes) };
+                                let _ = tx.try_send(message);
+                            }
+
+                            if fin {
+                                break;
+                            }
+                        },
+                        Err(quiche::Error::Done) => break,
+                        Err(_) => break
+                    }
+                }
+            }
+
+            let stream_ids: Vec<u64> = self.stream_map.keys().cloned().collect();
+
+            for stream_id in stream_ids {
+                loop {
+                    // Only pull from the application's write side while quiche can actually
+                    // accept more stream data; otherwise the bytes would just pile up in
+                    // quiche's own send buffer instead of the bounded channel.
+                    if self.inner.connect.stream_capacity(stream_id).unwrap_or(0) == 0 {
+                        break;
+                    }
+
+                    let message = match self.stream_map.get_mut(&stream_id) {
+                        Some((_, rx)) => rx.poll(),
+                        None => break
+                    };
+
+                    match message {
+                        Ok(Async::Ready(Some(Message::Bytes(bytes)))) => {
+                            let _ = self.inner.connect.stream_send(stream_id, &bytes, false);
+                        },
+                        Ok(Async::Ready(Some(Message::End(bytes)))) => {
+                            let _ = self.inner.connect.stream_send(stream_id, &bytes, true);
+                            self.stream_map.remove(&stream_id);
+                            break;
+                        },
+                        Ok(Async::Ready(Some(Message::Close))) | Ok(Async::Ready(None)) => {
+                            self.stream_map.remove(&stream_id);
+                            break;
+                        },
+                        Ok(Async::NotReady) => break,
+                        Err(_) => break
+                    }
                 }
             }
 
-            // TODO
+            try_ready!(self.inner.poll_complete().map_err(drop));
 
             if let Async::Ready(()) = self.close_recv.poll().map_err(drop)? {
                 return Ok(Async::Ready(()));